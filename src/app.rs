@@ -1,10 +1,13 @@
-use std::net::SocketAddr;
+use std::{collections::BTreeMap, net::SocketAddr};
 
 use futures::{future::BoxFuture, Future};
 use hyper::{http::request::Parts, Body, Request, Response};
 
 use crate::{
     client_ip,
+    compress::{self, CompressionConfig},
+    cookie,
+    cors::{self, CorsConfig},
     error::HttpResult,
     routing::{Route, Router},
 };
@@ -21,6 +24,18 @@ impl<S> Ctx<S> {
             .map(|s| s.to_string())
             .unwrap_or_else(|| self.addr.ip().to_string())
     }
+
+    /// Parse the `Cookie` header into a name→value map.
+    pub fn cookies(&self) -> BTreeMap<String, String> {
+        crate::str_header(&self.parts.headers, "cookie")
+            .map(cookie::parse)
+            .unwrap_or_default()
+    }
+
+    /// Look up a single cookie by name.
+    pub fn cookie(&self, name: &str) -> Option<String> {
+        self.cookies().remove(name)
+    }
 }
 
 pub struct App<S> {
@@ -33,6 +48,8 @@ pub struct App<S> {
             + 'static,
     >,
     post: Box<dyn Fn(Response<Body>) -> BoxFuture<'static, Response<Body>> + Send + Sync + 'static>,
+    compression: Option<CompressionConfig>,
+    cors: Option<CorsConfig>,
 }
 
 impl<S: Clone + Send + Sync + 'static> App<S> {
@@ -42,6 +59,8 @@ impl<S: Clone + Send + Sync + 'static> App<S> {
             router: Router::new(vec![]),
             pre: Box::new(|req| Box::pin(async { Ok(req) })),
             post: Box::new(|resp| Box::pin(async { resp })),
+            compression: None,
+            cors: None,
         }
     }
 
@@ -68,6 +87,25 @@ impl<S: Clone + Send + Sync + 'static> App<S> {
         self
     }
 
+    /// Negotiate `Accept-Encoding` and compress responses with the default
+    /// [`CompressionConfig`].
+    pub fn compress(self) -> Self {
+        self.compress_with(CompressionConfig::default())
+    }
+
+    /// Negotiate `Accept-Encoding` and compress responses per `config`.
+    pub fn compress_with(mut self, config: CompressionConfig) -> Self {
+        self.compression = Some(config);
+        self
+    }
+
+    /// Install a CORS layer that answers preflight requests and annotates
+    /// normal responses per `config`.
+    pub fn cors(mut self, config: CorsConfig) -> Self {
+        self.cors = Some(config);
+        self
+    }
+
     async fn router_fn(
         state: S,
         addr: SocketAddr,
@@ -81,8 +119,26 @@ impl<S: Clone + Send + Sync + 'static> App<S> {
     }
 
     pub async fn serve(&self, addr: SocketAddr, req: Request<Body>) -> Response<Body> {
+        // Stash request-only data before the request is consumed, since the post
+        // hook and the compression/CORS steps below only ever see the response.
+        let accept_encoding = self
+            .compression
+            .is_some()
+            .then(|| compress::accept_encoding(req.headers()))
+            .flatten();
+        let cors_origin = self.cors.is_some().then(|| cors::origin(req.headers())).flatten();
+
+        // CORS preflight short-circuits before the router, like a pre hook would.
+        let req = match self.cors.as_ref().and_then(|config| cors::preflight(config, &req)) {
+            Some(resp) => Err(resp),
+            None => Ok(req),
+        };
+
         // Pre hook
-        let req = (self.pre)(req).await;
+        let req = match req {
+            Ok(req) => (self.pre)(req).await,
+            Err(resp) => Err(resp),
+        };
 
         // Router
         let resp = match req {
@@ -93,6 +149,18 @@ impl<S: Clone + Send + Sync + 'static> App<S> {
         };
 
         // Post hook
-        (self.post)(resp).await
+        let resp = (self.post)(resp).await;
+
+        // CORS headers (preflight responses already carry their own)
+        let resp = match &self.cors {
+            Some(config) => cors::apply(config, cors_origin.as_deref(), resp),
+            None => resp,
+        };
+
+        // Compression
+        match &self.compression {
+            Some(config) => compress::apply(config, accept_encoding.as_deref(), resp).await,
+            None => resp,
+        }
     }
 }
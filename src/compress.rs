@@ -0,0 +1,93 @@
+use hyper::{body, header, Body, HeaderMap, Response};
+
+use crate::{compress, compress_deflate};
+
+/// Codings supported by [`negotiate`], in descending server preference order.
+const SUPPORTED_ENCODINGS: &[&str] = &["gzip", "deflate"];
+
+/// Configuration for the `Accept-Encoding` negotiating compression middleware
+/// installed with [`crate::app::App::compress`].
+#[derive(Clone, Copy, Debug)]
+pub struct CompressionConfig {
+    /// Bodies smaller than this are served uncompressed.
+    pub min_size: u64,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self { min_size: 1024 }
+    }
+}
+
+/// Pick the best coding we support among those the client accepts, preferring a
+/// higher q-value and, on a tie, our own preference order. `q=0` entries are
+/// treated as rejections. Returns `None` when nothing acceptable matches.
+fn negotiate(accept_encoding: &str) -> Option<&'static str> {
+    let accepted: Vec<(&str, f32)> = accept_encoding
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.trim().split(';');
+            let coding = parts.next()?.trim();
+            let q = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (!coding.is_empty()).then_some((coding, q))
+        })
+        .collect();
+    let wildcard_q = accepted.iter().find(|(c, _)| *c == "*").map(|(_, q)| *q);
+
+    SUPPORTED_ENCODINGS
+        .iter()
+        .enumerate()
+        .filter_map(|(pref, &supported)| {
+            let q = accepted
+                .iter()
+                .find(|(c, _)| c.eq_ignore_ascii_case(supported))
+                .map(|(_, q)| *q)
+                .or(wildcard_q)
+                .filter(|q| *q > 0.0)?;
+            Some((supported, q, pref))
+        })
+        .max_by(|(_, qa, pa), (_, qb, pb)| qa.partial_cmp(qb).unwrap().then(pb.cmp(pa)))
+        .map(|(coding, _, _)| coding)
+}
+
+/// Compress `response` according to `accept_encoding`, falling back to identity
+/// when nothing acceptable matches or the body is below `config.min_size`.
+pub(crate) async fn apply(
+    config: &CompressionConfig,
+    accept_encoding: Option<&str>,
+    response: Response<Body>,
+) -> Response<Body> {
+    let Some(coding) = accept_encoding.and_then(negotiate) else {
+        return response;
+    };
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+    if (bytes.len() as u64) < config.min_size {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+
+    let compressed = match coding {
+        "gzip" => compress(&bytes),
+        "deflate" => compress_deflate(&bytes),
+        _ => unreachable!("negotiate only returns supported codings"),
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(header::CONTENT_ENCODING, coding.parse().unwrap());
+    parts.headers.append(header::VARY, "Accept-Encoding".parse().unwrap());
+    Response::from_parts(parts, Body::from(compressed))
+}
+
+/// Read the request's `Accept-Encoding` header, if any, for later use by [`apply`].
+pub(crate) fn accept_encoding(map: &HeaderMap) -> Option<String> {
+    crate::str_header(map, "accept-encoding").map(str::to_string)
+}
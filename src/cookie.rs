@@ -0,0 +1,160 @@
+use std::{collections::BTreeMap, fmt::Write, time::SystemTime};
+
+use hyper::{
+    header::{self, HeaderValue},
+    http::response::Builder,
+    Body, Response,
+};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
+
+use crate::error::HttpResult;
+
+/// Parse a `Cookie` request header into a name→value map, URL-decoding values.
+pub(crate) fn parse(header: &str) -> BTreeMap<String, String> {
+    header
+        .split(';')
+        .filter_map(|pair| {
+            let (name, value) = pair.split_once('=')?;
+            let value = percent_decode_str(value.trim()).decode_utf8().ok()?;
+            Some((name.trim().to_string(), value.into_owned()))
+        })
+        .collect()
+}
+
+/// `SameSite` attribute of a [`Cookie`].
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl SameSite {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+/// A `Set-Cookie` builder.
+pub struct Cookie {
+    name: String,
+    value: String,
+    path: Option<String>,
+    domain: Option<String>,
+    max_age: Option<i64>,
+    expires: Option<SystemTime>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+}
+
+impl Cookie {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            path: None,
+            domain: None,
+            max_age: None,
+            expires: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        }
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    pub fn max_age(mut self, seconds: i64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    pub fn expires(mut self, time: SystemTime) -> Self {
+        self.expires = Some(time);
+        self
+    }
+
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Render this cookie as a `Set-Cookie` header value.
+    pub fn to_header_value(&self) -> HeaderValue {
+        let mut out = format!(
+            "{}={}",
+            self.name,
+            utf8_percent_encode(&self.value, NON_ALPHANUMERIC)
+        );
+        if let Some(path) = &self.path {
+            write!(out, "; Path={path}").unwrap();
+        }
+        if let Some(domain) = &self.domain {
+            write!(out, "; Domain={domain}").unwrap();
+        }
+        if let Some(max_age) = self.max_age {
+            write!(out, "; Max-Age={max_age}").unwrap();
+        }
+        if let Some(expires) = self.expires {
+            write!(out, "; Expires={}", httpdate::fmt_http_date(expires)).unwrap();
+        }
+        if self.secure {
+            out.push_str("; Secure");
+        }
+        if self.http_only {
+            out.push_str("; HttpOnly");
+        }
+        if let Some(same_site) = &self.same_site {
+            write!(out, "; SameSite={}", same_site.as_str()).unwrap();
+        }
+        HeaderValue::from_str(&out).unwrap()
+    }
+}
+
+/// Append one or more `Set-Cookie` headers, since a response may set several
+/// cookies at once. Each call adds a header and keeps any already present.
+pub trait SetCookie: Sized {
+    fn cookie(self, cookie: Cookie) -> Self;
+}
+
+impl SetCookie for Response<Body> {
+    fn cookie(mut self, cookie: Cookie) -> Self {
+        self.headers_mut()
+            .append(header::SET_COOKIE, cookie.to_header_value());
+        self
+    }
+}
+
+impl SetCookie for Builder {
+    fn cookie(self, cookie: Cookie) -> Self {
+        self.header(header::SET_COOKIE, cookie.to_header_value())
+    }
+}
+
+impl SetCookie for HttpResult {
+    fn cookie(self, cookie: Cookie) -> Self {
+        self.map(|response| response.cookie(cookie))
+    }
+}
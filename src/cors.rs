@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use hyper::{
+    header::{self, HeaderName},
+    http::Method,
+    Body, HeaderMap, Request, Response, StatusCode,
+};
+
+/// Origins allowed to make cross-origin requests, configured on [`CorsConfig`].
+pub enum AllowedOrigins {
+    /// Allow any origin. Answered with `*` unless credentials are required, in
+    /// which case the request's `Origin` is echoed back instead, since the spec
+    /// forbids pairing `*` with credentials.
+    Any,
+    /// Allow only the listed origins, each echoed back exactly when matched.
+    List(HashSet<String>),
+}
+
+/// Configuration for the CORS layer installed with [`crate::app::App::cors`].
+pub struct CorsConfig {
+    pub allowed_origins: AllowedOrigins,
+    pub allowed_methods: Vec<Method>,
+    pub allowed_headers: Vec<HeaderName>,
+    pub exposed_headers: Vec<HeaderName>,
+    pub max_age: Option<u64>,
+    pub credentials: bool,
+}
+
+impl CorsConfig {
+    /// `Access-Control-Allow-Origin` value for `origin` plus whether it must be
+    /// accompanied by `Vary: Origin` (true whenever we echoed the origin back
+    /// rather than answering with the static `*`).
+    fn allow_origin(&self, origin: &str) -> Option<(&str, bool)> {
+        match &self.allowed_origins {
+            AllowedOrigins::Any if !self.credentials => Some(("*", false)),
+            AllowedOrigins::Any => Some((origin, true)),
+            AllowedOrigins::List(origins) => origins.contains(origin).then_some((origin, true)),
+        }
+    }
+}
+
+fn join<'a>(items: impl Iterator<Item = &'a str>) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
+
+pub(crate) fn origin(map: &HeaderMap) -> Option<String> {
+    crate::str_header(map, "origin").map(str::to_string)
+}
+
+/// Answer an `OPTIONS` preflight carrying `Access-Control-Request-Method` with a
+/// `204` response advertising the computed `Access-Control-Allow-*` headers.
+/// Returns `None` for anything else, including a preflight for a disallowed
+/// origin or method, so the caller falls through to normal request handling.
+pub(crate) fn preflight(config: &CorsConfig, req: &Request<Body>) -> Option<Response<Body>> {
+    if req.method() != Method::OPTIONS {
+        return None;
+    }
+    let requested_method = crate::str_header(req.headers(), "access-control-request-method")?;
+    let requested_method: Method = requested_method.parse().ok()?;
+    if !config.allowed_methods.contains(&requested_method) {
+        return None;
+    }
+    let origin = crate::str_header(req.headers(), "origin")?;
+    let (allow_origin, vary) = config.allow_origin(origin)?;
+
+    let mut builder = Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin)
+        .header(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            join(config.allowed_methods.iter().map(Method::as_str)),
+        )
+        .header(
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            join(config.allowed_headers.iter().map(HeaderName::as_str)),
+        );
+    if vary {
+        builder = builder.header(header::VARY, "Origin");
+    }
+    if config.credentials {
+        builder = builder.header(header::ACCESS_CONTROL_ALLOW_CREDENTIALS, "true");
+    }
+    if let Some(max_age) = config.max_age {
+        builder = builder.header(header::ACCESS_CONTROL_MAX_AGE, max_age.to_string());
+    }
+    Some(builder.body(Body::empty()).unwrap())
+}
+
+/// Inject the CORS response headers for a normal (non-preflight) request.
+/// Disallowed origins are left untouched so the browser blocks the response.
+pub(crate) fn apply(
+    config: &CorsConfig,
+    origin: Option<&str>,
+    mut response: Response<Body>,
+) -> Response<Body> {
+    let Some(origin) = origin else {
+        return response;
+    };
+    let Some((allow_origin, vary)) = config.allow_origin(origin) else {
+        return response;
+    };
+
+    let headers = response.headers_mut();
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_ORIGIN,
+        allow_origin.parse().unwrap(),
+    );
+    if vary {
+        headers.insert(header::VARY, "Origin".parse().unwrap());
+    }
+    if config.credentials {
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+            "true".parse().unwrap(),
+        );
+    }
+    if !config.exposed_headers.is_empty() {
+        headers.insert(
+            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            join(config.exposed_headers.iter().map(HeaderName::as_str))
+                .parse()
+                .unwrap(),
+        );
+    }
+    response
+}
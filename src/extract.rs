@@ -0,0 +1,202 @@
+use std::{collections::BTreeMap, future::Future};
+
+use duplicate::duplicate_item;
+use futures::future::BoxFuture;
+use hyper::{body::Bytes, http::Method, Body, StatusCode};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    app::Ctx,
+    body_bytes_max,
+    error::{ErrorHelper, HttpError, HttpResult},
+    routing::Route,
+};
+
+/// Route params, as handed to handlers by the router.
+pub type Params = BTreeMap<String, String>;
+
+/// Maximum body size read by the body-based extractors below.
+const MAX_BODY_SIZE: u64 = 2 * 1024 * 1024;
+
+/// Pull a typed value out of `(Ctx<S>, Body, Params)`. Implementors hand back
+/// whatever they didn't consume so the next extractor in the chain can use
+/// it; a body-consuming extractor (like [`Json`]) hands back an empty
+/// [`Body`] in its place, since it can only be read once.
+pub trait FromRequest<S>: Sized + Send + 'static {
+    fn from_request(
+        ctx: Ctx<S>,
+        body: Body,
+        params: Params,
+    ) -> BoxFuture<'static, Result<(Self, Ctx<S>, Body, Params), HttpError>>;
+}
+
+/// Deserialize the request body as JSON.
+pub struct Json<T>(pub T);
+
+impl<S, T> FromRequest<S> for Json<T>
+where
+    S: Send + Sync + 'static,
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_request(
+        ctx: Ctx<S>,
+        body: Body,
+        params: Params,
+    ) -> BoxFuture<'static, Result<(Self, Ctx<S>, Body, Params), HttpError>> {
+        Box::pin(async move {
+            let bytes = body_bytes_max(body, MAX_BODY_SIZE)
+                .await
+                .status(StatusCode::BAD_REQUEST)?
+                .ok_or_else(|| HttpError::status(StatusCode::PAYLOAD_TOO_LARGE))?;
+            let value = serde_json::from_slice(&bytes).status(StatusCode::BAD_REQUEST)?;
+            Ok((Json(value), ctx, Body::empty(), params))
+        })
+    }
+}
+
+/// Deserialize the request URI's query string.
+pub struct Query<T>(pub T);
+
+impl<S, T> FromRequest<S> for Query<T>
+where
+    S: Send + Sync + 'static,
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_request(
+        ctx: Ctx<S>,
+        body: Body,
+        params: Params,
+    ) -> BoxFuture<'static, Result<(Self, Ctx<S>, Body, Params), HttpError>> {
+        Box::pin(async move {
+            let value = serde_urlencoded::from_str(ctx.parts.uri.query().unwrap_or(""))
+                .status(StatusCode::BAD_REQUEST)?;
+            Ok((Query(value), ctx, body, params))
+        })
+    }
+}
+
+/// Parse the route's single dynamic segment into `T`. Only meaningful on
+/// routes with exactly one param; read the raw `Params` map directly if a
+/// route needs more than that.
+pub struct Path<T>(pub T);
+
+impl<S, T> FromRequest<S> for Path<T>
+where
+    S: Send + Sync + 'static,
+    T: std::str::FromStr + Send + 'static,
+    T::Err: std::error::Error,
+{
+    fn from_request(
+        ctx: Ctx<S>,
+        body: Body,
+        params: Params,
+    ) -> BoxFuture<'static, Result<(Self, Ctx<S>, Body, Params), HttpError>> {
+        Box::pin(async move {
+            let value = params
+                .values()
+                .next()
+                .status(StatusCode::BAD_REQUEST)?
+                .parse::<T>()
+                .status(StatusCode::BAD_REQUEST)?;
+            Ok((Path(value), ctx, body, params))
+        })
+    }
+}
+
+impl<S: Send + Sync + 'static> FromRequest<S> for Bytes {
+    fn from_request(
+        ctx: Ctx<S>,
+        body: Body,
+        params: Params,
+    ) -> BoxFuture<'static, Result<(Self, Ctx<S>, Body, Params), HttpError>> {
+        Box::pin(async move {
+            let bytes = body_bytes_max(body, MAX_BODY_SIZE)
+                .await
+                .status(StatusCode::BAD_REQUEST)?
+                .ok_or_else(|| HttpError::status(StatusCode::PAYLOAD_TOO_LARGE))?;
+            Ok((bytes, ctx, Body::empty(), params))
+        })
+    }
+}
+
+impl<S: Send + Sync + 'static> FromRequest<S> for String {
+    fn from_request(
+        ctx: Ctx<S>,
+        body: Body,
+        params: Params,
+    ) -> BoxFuture<'static, Result<(Self, Ctx<S>, Body, Params), HttpError>> {
+        Box::pin(async move {
+            let bytes = body_bytes_max(body, MAX_BODY_SIZE)
+                .await
+                .status(StatusCode::BAD_REQUEST)?
+                .ok_or_else(|| HttpError::status(StatusCode::PAYLOAD_TOO_LARGE))?;
+            let value = String::from_utf8(bytes.to_vec()).status(StatusCode::BAD_REQUEST)?;
+            Ok((value, ctx, Body::empty(), params))
+        })
+    }
+}
+
+/// A typed handler whose arguments are pulled out of the request via
+/// [`FromRequest`], implemented for functions of up to 4 extractors by the
+/// `impl_handler!` invocations below.
+pub trait Handler<S, Args>: Clone + Send + Sync + 'static {
+    fn call(self, ctx: Ctx<S>, body: Body, params: Params) -> BoxFuture<'static, HttpResult>;
+}
+
+macro_rules! impl_handler {
+    ($($arg:ident),*) => {
+        impl<S, F, Fut, $($arg),*> Handler<S, ($($arg,)*)> for F
+        where
+            S: Send + Sync + 'static,
+            F: Fn($($arg),*) -> Fut + Clone + Send + Sync + 'static,
+            Fut: Future<Output = HttpResult> + Send + 'static,
+            $($arg: FromRequest<S>,)*
+        {
+            #[allow(unused_variables)]
+            fn call(self, ctx: Ctx<S>, body: Body, params: Params) -> BoxFuture<'static, HttpResult> {
+                Box::pin(async move {
+                    $(let ($arg, ctx, body, params) = $arg::from_request(ctx, body, params).await?;)*
+                    self($($arg),*).await
+                })
+            }
+        }
+    };
+}
+
+impl_handler!();
+impl_handler!(A);
+impl_handler!(A, B);
+impl_handler!(A, B, C);
+impl_handler!(A, B, C, D);
+
+impl<S: Send + Sync + 'static> Route<(Ctx<S>, Body)> {
+    /// Build a [`Route`] from a typed `handler`, running its [`FromRequest`]
+    /// extractors in order and returning early with their error response if
+    /// any fails. The raw `(Ctx<S>, Body)` signature handled by [`Route::add`]
+    /// remains the lowest-level escape hatch.
+    pub fn handler<H, Args>(method: Method, handler: H) -> Self
+    where
+        H: Handler<S, Args>,
+    {
+        Route::new().add(method, move |(ctx, body): (Ctx<S>, Body), params| {
+            let handler = handler.clone();
+            async move { handler.call(ctx, body, params).await }
+        })
+    }
+}
+
+#[duplicate_item(
+        fun      method;
+        [get]    [GET];
+        [post]   [POST];
+        [put]    [PUT];
+        [delete] [DELETE];
+        [patch]  [PATCH]
+      )]
+pub fn fun<S, H, Args>(handler: H) -> Route<(Ctx<S>, Body)>
+where
+    S: Send + Sync + 'static,
+    H: Handler<S, Args>,
+{
+    Route::handler(Method::method, handler)
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::BTreeMap,
+    io::SeekFrom,
+    path::{Component, Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use hyper::{
+    header,
+    http::{response::Parts, StatusCode},
+    Body, HeaderMap, Response,
+};
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt},
+};
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    app::Ctx,
+    conditional_handle, etag,
+    error::{ErrorHelper, HttpError, HttpResult},
+    routing::{self, Route},
+};
+
+/// An open file ready to be turned into a response, carrying the metadata
+/// needed for conditional requests and range handling.
+pub struct NamedFile {
+    file: File,
+    len: u64,
+    etag: String,
+    last_modified: String,
+    content_type: String,
+}
+
+impl NamedFile {
+    /// Open `path` asynchronously, rejecting anything that isn't a regular
+    /// file with a `404` (matching the response a missing file would give).
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, HttpError> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .await
+            .map_err(|_| HttpError::status(StatusCode::NOT_FOUND))?;
+        let metadata = file
+            .metadata()
+            .await
+            .map_err(|_| HttpError::status(StatusCode::NOT_FOUND))?;
+        if !metadata.is_file() {
+            return Err(HttpError::status(StatusCode::NOT_FOUND));
+        }
+
+        let len = metadata.len();
+        let mtime = metadata.modified().unwrap_or(UNIX_EPOCH);
+        // Hash length + mtime rather than the file content, so large files get a
+        // cheap etag instead of a full read.
+        let mut meta = [0; 16];
+        meta[..8].copy_from_slice(&len.to_le_bytes());
+        let secs = mtime.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        meta[8..].copy_from_slice(&secs.to_le_bytes());
+
+        Ok(Self {
+            file,
+            len,
+            etag: etag(&meta),
+            last_modified: httpdate::fmt_http_date(mtime),
+            content_type: mime_guess::from_path(path).first_or_octet_stream().to_string(),
+        })
+    }
+
+    /// Turn this file into a response, honoring `If-None-Match` /
+    /// `If-Modified-Since` (a `304`) and a single `Range` request (a `206` or
+    /// a `416` when unsatisfiable). The body is streamed, never buffered
+    /// whole.
+    pub async fn into_response(mut self, headers: &HeaderMap) -> HttpResult {
+        let builder = Response::builder()
+            .header(header::CONTENT_TYPE, self.content_type.clone())
+            .header(header::ETAG, self.etag.clone())
+            .header(header::LAST_MODIFIED, self.last_modified.clone())
+            .header(header::ACCEPT_RANGES, "bytes");
+        let placeholder = conditional_handle(headers, builder.body(Body::empty()).unwrap());
+        if placeholder.status() == StatusCode::NOT_MODIFIED {
+            return Ok(placeholder);
+        }
+        let (parts, _) = placeholder.into_parts();
+
+        if let Some(spec) = headers
+            .get(header::RANGE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|range| parse_range(range, self.len))
+        {
+            return self.range_response(parts, spec).await;
+        }
+
+        let len = self.len;
+        let body = Body::wrap_stream(ReaderStream::new(self.file));
+        let mut response = Response::from_parts(parts, body);
+        response
+            .headers_mut()
+            .insert(header::CONTENT_LENGTH, len.into());
+        Ok(response)
+    }
+
+    async fn range_response(mut self, parts: Parts, spec: RangeSpec) -> HttpResult {
+        let len = self.len;
+        if spec.start >= len {
+            let mut response = Response::from_parts(parts, Body::empty());
+            *response.status_mut() = StatusCode::RANGE_NOT_SATISFIABLE;
+            response.headers_mut().insert(
+                header::CONTENT_RANGE,
+                format!("bytes */{len}").parse().unwrap(),
+            );
+            return Ok(response);
+        }
+
+        let end = spec.end.min(len - 1);
+        let chunk_len = end - spec.start + 1;
+        self.file
+            .seek(SeekFrom::Start(spec.start))
+            .await
+            .status(StatusCode::INTERNAL_SERVER_ERROR)?;
+        let body = Body::wrap_stream(ReaderStream::new(self.file.take(chunk_len)));
+
+        let mut response = Response::from_parts(parts, body);
+        *response.status_mut() = StatusCode::PARTIAL_CONTENT;
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_RANGE,
+            format!("bytes {}-{}/{}", spec.start, end, len)
+                .parse()
+                .unwrap(),
+        );
+        headers.insert(header::CONTENT_LENGTH, chunk_len.into());
+        Ok(response)
+    }
+}
+
+struct RangeSpec {
+    start: u64,
+    end: u64,
+}
+
+/// Parse a single `bytes=start-end` range (including the `start-` and
+/// `-suffix` forms). Anything else — a list of ranges, a unit other than
+/// `bytes`, garbage — returns `None` so the caller falls back to serving the
+/// whole file, per RFC 7233's guidance to ignore a malformed `Range`.
+fn parse_range(value: &str, len: u64) -> Option<RangeSpec> {
+    let value = value.strip_prefix("bytes=")?;
+    if value.contains(',') {
+        return None;
+    }
+    let (start, end) = value.split_once('-')?;
+    let spec = match (start, end) {
+        ("", suffix) => {
+            let suffix_len: u64 = suffix.parse().ok()?;
+            RangeSpec {
+                start: len.saturating_sub(suffix_len),
+                end: len.saturating_sub(1),
+            }
+        }
+        (start, "") => {
+            let start: u64 = start.parse().ok()?;
+            // A start at or past EOF is kept so the caller answers it with a 416.
+            RangeSpec {
+                start,
+                end: len.saturating_sub(1).max(start),
+            }
+        }
+        (start, end) => RangeSpec {
+            start: start.parse().ok()?,
+            end: end.parse().ok()?,
+        },
+    };
+    (spec.start <= spec.end).then_some(spec)
+}
+
+/// Join `root` with the request's path param, rejecting `..`, an absolute
+/// path, or a percent-encoded separator trying to escape `root`.
+fn resolve_path(root: &Path, requested: &str) -> Result<PathBuf, HttpError> {
+    let decoded = percent_encoding::percent_decode_str(requested)
+        .decode_utf8()
+        .map_err(|_| HttpError::status(StatusCode::NOT_FOUND))?;
+
+    let mut path = root.to_path_buf();
+    for component in Path::new(decoded.as_ref()).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(HttpError::status(StatusCode::NOT_FOUND))
+            }
+        }
+    }
+    Ok(path)
+}
+
+/// Serve a single fixed file for every request on the route.
+pub fn serve_file<S: Send + Sync + 'static>(path: impl Into<PathBuf>) -> Route<(Ctx<S>, Body)> {
+    let path = path.into();
+    routing::get(move |(ctx, _): (Ctx<S>, Body), _: BTreeMap<String, String>| {
+        let path = path.clone();
+        async move { NamedFile::open(path).await?.into_response(&ctx.parts.headers).await }
+    })
+}
+
+/// Serve `root` as a directory tree. Register the route with a single
+/// wildcard param (e.g. `"/static/*path"`); the captured param is resolved
+/// against `root` with [`resolve_path`].
+pub fn serve_dir<S: Send + Sync + 'static>(root: impl Into<PathBuf>) -> Route<(Ctx<S>, Body)> {
+    let root = root.into();
+    routing::get(move |(ctx, _): (Ctx<S>, Body), params: BTreeMap<String, String>| {
+        let root = root.clone();
+        async move {
+            let requested = params.values().next().map(String::as_str).unwrap_or("");
+            let path = resolve_path(&root, requested)?;
+            NamedFile::open(path).await?.into_response(&ctx.parts.headers).await
+        }
+    })
+}
@@ -6,7 +6,12 @@ use hyper::{
 use libdeflater::{CompressionLvl, Compressor};
 
 pub mod app;
+pub mod compress;
+pub mod cookie;
+pub mod cors;
 pub mod error;
+pub mod extract;
+pub mod files;
 pub mod routing;
 
 pub use base64;
@@ -86,6 +91,16 @@ pub fn compress(in_data: &[u8]) -> Vec<u8> {
     gzip
 }
 
+/// Fast in memory raw deflate compression
+pub fn compress_deflate(in_data: &[u8]) -> Vec<u8> {
+    let mut compressor = Compressor::new(CompressionLvl::default());
+    let max_size = compressor.deflate_compress_bound(in_data.len());
+    let mut deflate = vec![0; max_size];
+    let deflate_size = compressor.deflate_compress(in_data, &mut deflate).unwrap();
+    deflate.resize(deflate_size, 0);
+    deflate
+}
+
 /// Handle matching etag by changing status code and removing body
 pub fn etag_handle(map: &HeaderMap, mut response: Response<Body>) -> Response<Body> {
     let rmap = response.headers();
@@ -100,8 +115,51 @@ pub fn etag_handle(map: &HeaderMap, mut response: Response<Body>) -> Response<Bo
     response
 }
 
+/// Handle matching last modified date by changing status code and removing body
+///
+/// Compares at whole-second resolution, as HTTP-dates carry no sub-second precision.
+pub fn last_modified_handle(map: &HeaderMap, mut response: Response<Body>) -> Response<Body> {
+    let rmap = response.headers();
+    let matched = map
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|h| h.to_str().ok())
+        .and_then(parse_http_date)
+        .zip(
+            rmap.get(header::LAST_MODIFIED)
+                .and_then(|h| h.to_str().ok())
+                .and_then(parse_http_date),
+        )
+        .map(|(since, last_modified)| last_modified <= since)
+        .unwrap_or(false);
+    if matched {
+        *response.status_mut() = StatusCode::NOT_MODIFIED;
+        *response.body_mut() = Body::empty();
+    }
+    response
+}
+
+/// Handle conditional requests, following the standard precedence rule: when both
+/// `If-None-Match` and `If-Modified-Since` are present, `If-None-Match` wins and the
+/// date check is ignored entirely.
+pub fn conditional_handle(map: &HeaderMap, response: Response<Body>) -> Response<Body> {
+    if map.contains_key(header::IF_NONE_MATCH) {
+        etag_handle(map, response)
+    } else {
+        last_modified_handle(map, response)
+    }
+}
+
+/// Parse an RFC 7231 IMF-fixdate (e.g. `Sun, 06 Nov 1994 08:49:37 GMT`) into seconds
+/// since the Unix epoch. Returns `None` instead of erroring on anything unparseable,
+/// so an invalid date is simply treated as "not matched".
+fn parse_http_date(date: &str) -> Option<i64> {
+    let time = httpdate::parse_http_date(date).ok()?;
+    let secs = time.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+    Some(secs as i64)
+}
+
 /// Generate strong etag from bytes
-fn etag(bytes: &[u8]) -> String {
+pub(crate) fn etag(bytes: &[u8]) -> String {
     let mut buf = [b'"'; 24];
     let hash = xxhash_rust::xxh3::xxh3_128(bytes);
     base64::encode_config_slice(hash.to_le_bytes(), base64::URL_SAFE_NO_PAD, &mut buf[1..24]);
@@ -56,7 +56,7 @@ impl<T> Route<T> {
         self.add(Method::method, handler)
     }
 
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             methods: BTreeMap::new(),
         }